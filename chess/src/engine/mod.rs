@@ -0,0 +1,211 @@
+use crate::game::Game;
+use crate::{Color, Move, PieceType, Pos};
+
+#[cfg(test)]
+mod tests;
+
+const PAWN_VALUE: i32 = 100;
+const KNIGHT_VALUE: i32 = 320;
+const BISHOP_VALUE: i32 = 330;
+const ROOK_VALUE: i32 = 500;
+const QUEEN_VALUE: i32 = 900;
+const KING_VALUE: i32 = 0;
+
+/// Comfortably above any realistic material score, so a mate found deeper
+/// still scores worse than one found in fewer plies.
+const MATE_SCORE: i32 = 1_000_000;
+
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10,-20,-20, 10, 10,  5,
+     5, -5,-10,  0,  0,-10, -5,  5,
+     0,  0,  0, 20, 20,  0,  0,  0,
+     5,  5, 10, 25, 25, 10,  5,  5,
+    10, 10, 20, 30, 30, 20, 10, 10,
+    50, 50, 50, 50, 50, 50, 50, 50,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+    -50,-40,-30,-30,-30,-30,-40,-50,
+    -40,-20,  0,  5,  5,  0,-20,-40,
+    -30,  5, 10, 15, 15, 10,  5,-30,
+    -30,  0, 15, 20, 20, 15,  0,-30,
+    -30,  5, 15, 20, 20, 15,  5,-30,
+    -30,  0, 10, 15, 15, 10,  0,-30,
+    -40,-20,  0,  0,  0,  0,-20,-40,
+    -50,-40,-30,-30,-30,-30,-40,-50,
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; 64] = [
+    -20,-10,-10,-10,-10,-10,-10,-20,
+    -10,  5,  0,  0,  0,  0,  5,-10,
+    -10, 10, 10, 10, 10, 10, 10,-10,
+    -10,  0, 10, 10, 10, 10,  0,-10,
+    -10,  5,  5, 10, 10,  5,  5,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -20,-10,-10,-10,-10,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; 64] = [
+     0,  0,  0,  5,  5,  0,  0,  0,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     5, 10, 10, 10, 10, 10, 10,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; 64] = [
+    -20,-10,-10, -5, -5,-10,-10,-20,
+    -10,  0,  5,  0,  0,  0,  0,-10,
+    -10,  5,  5,  5,  5,  5,  0,-10,
+      0,  0,  5,  5,  5,  5,  0, -5,
+     -5,  0,  5,  5,  5,  5,  0, -5,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -20,-10,-10, -5, -5,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const KING_TABLE: [i32; 64] = [
+     20, 30, 10,  0,  0, 10, 30, 20,
+     20, 20,  0,  0,  0,  0, 20, 20,
+    -10,-20,-20,-20,-20,-20,-20,-10,
+    -20,-30,-30,-40,-40,-30,-30,-20,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+];
+
+fn material_value(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::Pawn => PAWN_VALUE,
+        PieceType::Knight => KNIGHT_VALUE,
+        PieceType::Bishop => BISHOP_VALUE,
+        PieceType::Rook => ROOK_VALUE,
+        PieceType::Queen => QUEEN_VALUE,
+        PieceType::King => KING_VALUE,
+    }
+}
+
+fn piece_square_value(piece_type: PieceType, color: Color, pos: Pos) -> i32 {
+    // The tables are written from White's side of the board, so Black looks
+    // up the vertically mirrored square.
+    let index = match color {
+        Color::White => pos.index(),
+        Color::Black => pos.index() ^ 56,
+    };
+
+    let table = match piece_type {
+        PieceType::Pawn => &PAWN_TABLE,
+        PieceType::Knight => &KNIGHT_TABLE,
+        PieceType::Bishop => &BISHOP_TABLE,
+        PieceType::Rook => &ROOK_TABLE,
+        PieceType::Queen => &QUEEN_TABLE,
+        PieceType::King => &KING_TABLE,
+    };
+
+    table[index]
+}
+
+/// Static evaluation from the perspective of the side to move: positive is
+/// good for whoever is on the move.
+fn evaluate(game: &Game) -> i32 {
+    let mut score = 0;
+
+    for i in 0..64u8 {
+        let piece = game.at_index(i as usize);
+        if piece.is_empty() {
+            continue;
+        }
+
+        let pos = Pos::new_index(i);
+        let piece_value =
+            material_value(piece.get_type()) + piece_square_value(piece.get_type(), piece.color(), pos);
+
+        score += if piece.color() == game.current_color() {
+            piece_value
+        } else {
+            -piece_value
+        };
+    }
+
+    score
+}
+
+fn negamax(game: &mut Game, depth: u8, mut alpha: i32, beta: i32) -> i32 {
+    if depth == 0 {
+        return evaluate(game);
+    }
+
+    let moves = game.legal_moves();
+    if moves.is_empty() {
+        return if game.in_check() {
+            // `depth` is plies remaining, so a mate found with more of the
+            // budget left over was reached in fewer plies from the root:
+            // add it to MATE_SCORE rather than subtract, so shorter mates
+            // score more extremely and are preferred over slower ones.
+            -(MATE_SCORE + depth as i32)
+        } else {
+            0
+        };
+    }
+
+    let mut best = i32::MIN;
+    for (from, r#move) in moves {
+        game.play(from, r#move);
+        let score = -negamax(game, depth - 1, -beta, -alpha);
+        game.undo(0);
+
+        if score > best {
+            best = score;
+        }
+        if best > alpha {
+            alpha = best;
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    best
+}
+
+impl Game {
+    /// Searches `depth` plies with negamax/alpha-beta and returns the best
+    /// move found for the side to move. Every move tried is undone again,
+    /// so the game is left in the position it was called with.
+    ///
+    /// Deliberately returns `Option<(Pos, Move)>` rather than a bare tuple:
+    /// with no legal moves (checkmate/stalemate) there is no move to play,
+    /// and the UCI front-end relies on `None` to report `bestmove 0000`.
+    pub fn best_move(&mut self, depth: u8) -> Option<(Pos, Move)> {
+        let moves = self.legal_moves();
+
+        let mut best_score = i32::MIN;
+        let mut best_move = None;
+
+        for (from, r#move) in moves {
+            self.play(from, r#move);
+            let score = -negamax(self, depth.saturating_sub(1), i32::MIN + 1, i32::MAX - 1);
+            self.undo(0);
+
+            if best_move.is_none() || score > best_score {
+                best_score = score;
+                best_move = Some((from, r#move));
+            }
+        }
+
+        best_move
+    }
+}
@@ -0,0 +1,19 @@
+use super::*;
+
+#[test]
+fn checkmate_score_grows_with_remaining_depth() {
+    // Fool's mate: White is checkmated on move 3, to move.
+    let mut game =
+        Game::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3").unwrap();
+
+    assert!(game.in_check());
+    assert!(game.legal_moves().is_empty());
+
+    let shallow = negamax(&mut game, 1, i32::MIN + 1, i32::MAX - 1);
+    let deep = negamax(&mut game, 3, i32::MIN + 1, i32::MAX - 1);
+
+    // More remaining depth at the same terminal node means the mate was
+    // reached in fewer plies from the root, so it should score worse for
+    // the side being mated (more negative).
+    assert!(deep < shallow);
+}
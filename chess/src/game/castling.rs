@@ -0,0 +1,68 @@
+use crate::Color;
+
+const WHITE_KINGSIDE: u8 = 0b0001;
+const WHITE_QUEENSIDE: u8 = 0b0010;
+const BLACK_KINGSIDE: u8 = 0b0100;
+const BLACK_QUEENSIDE: u8 = 0b1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CastlingRights(u8);
+
+impl CastlingRights {
+    pub fn all() -> Self {
+        CastlingRights(WHITE_KINGSIDE | WHITE_QUEENSIDE | BLACK_KINGSIDE | BLACK_QUEENSIDE)
+    }
+
+    pub fn none() -> Self {
+        CastlingRights(0)
+    }
+
+    pub fn kingside(&self, color: Color) -> bool {
+        self.0 & Self::kingside_bit(color) != 0
+    }
+
+    pub fn queenside(&self, color: Color) -> bool {
+        self.0 & Self::queenside_bit(color) != 0
+    }
+
+    pub fn grant_kingside(&mut self, color: Color) {
+        self.0 |= Self::kingside_bit(color);
+    }
+
+    pub fn grant_queenside(&mut self, color: Color) {
+        self.0 |= Self::queenside_bit(color);
+    }
+
+    pub fn revoke_kingside(&mut self, color: Color) {
+        self.0 &= !Self::kingside_bit(color);
+    }
+
+    pub fn revoke_queenside(&mut self, color: Color) {
+        self.0 &= !Self::queenside_bit(color);
+    }
+
+    pub fn revoke_all(&mut self, color: Color) {
+        self.revoke_kingside(color);
+        self.revoke_queenside(color);
+    }
+
+    fn kingside_bit(color: Color) -> u8 {
+        match color {
+            Color::White => WHITE_KINGSIDE,
+            Color::Black => BLACK_KINGSIDE,
+        }
+    }
+
+    fn queenside_bit(color: Color) -> u8 {
+        match color {
+            Color::White => WHITE_QUEENSIDE,
+            Color::Black => BLACK_QUEENSIDE,
+        }
+    }
+}
+
+impl Default for CastlingRights {
+    fn default() -> Self {
+        Self::all()
+    }
+}
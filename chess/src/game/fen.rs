@@ -0,0 +1,221 @@
+use crate::{Board, Color, PieceType, Pos, TaggedPiece};
+
+use super::castling::CastlingRights;
+
+pub type ParsedFen = (Board, Color, CastlingRights, Option<Pos>, u16, u16);
+
+pub fn parse_fen(fen: &str) -> Result<ParsedFen, &'static str> {
+    let mut fields = fen.split_whitespace();
+
+    let placement = fields.next().ok_or("FEN is missing piece placement")?;
+    let active_color = fields.next().ok_or("FEN is missing active color")?;
+    let castling = fields.next().ok_or("FEN is missing castling availability")?;
+    let en_passant = fields.next().ok_or("FEN is missing en passant target")?;
+    let halfmove_clock = fields.next().ok_or("FEN is missing halfmove clock")?;
+    let fullmove_number = fields.next().ok_or("FEN is missing fullmove number")?;
+
+    let board = parse_placement(placement)?;
+    let player = parse_color(active_color)?;
+    let castling_rights = parse_castling(castling)?;
+    let en_passant = parse_en_passant(en_passant)?;
+    let halfmove_clock: u16 = halfmove_clock.parse().map_err(|_| "invalid halfmove clock in FEN")?;
+    let fullmove_number: u16 = fullmove_number
+        .parse()
+        .map_err(|_| "invalid fullmove number in FEN")?;
+
+    Ok((board, player, castling_rights, en_passant, halfmove_clock, fullmove_number))
+}
+
+fn parse_placement(placement: &str) -> Result<Board, &'static str> {
+    let mut board = Board::empty();
+    let ranks: Vec<&str> = placement.split('/').collect();
+
+    if ranks.len() != 8 {
+        return Err("FEN piece placement must have 8 ranks");
+    }
+
+    for (rank_from_top, rank) in ranks.iter().enumerate() {
+        let y = 7 - rank_from_top as u8;
+        let mut x = 0u8;
+
+        for c in rank.chars() {
+            if let Some(empty_squares) = c.to_digit(10) {
+                x += empty_squares as u8;
+            } else {
+                if x >= 8 {
+                    return Err("FEN rank does not cover all 8 files");
+                }
+
+                let color = if c.is_uppercase() { Color::White } else { Color::Black };
+                let piece_type = parse_piece_type(c)?;
+                board.place(Pos::from_xy(x, y), TaggedPiece::new(piece_type, color));
+                x += 1;
+            }
+        }
+
+        if x != 8 {
+            return Err("FEN rank does not cover all 8 files");
+        }
+    }
+
+    Ok(board)
+}
+
+fn parse_piece_type(c: char) -> Result<PieceType, &'static str> {
+    match c.to_ascii_lowercase() {
+        'p' => Ok(PieceType::Pawn),
+        'n' => Ok(PieceType::Knight),
+        'b' => Ok(PieceType::Bishop),
+        'r' => Ok(PieceType::Rook),
+        'q' => Ok(PieceType::Queen),
+        'k' => Ok(PieceType::King),
+        _ => Err("unrecognised piece letter in FEN"),
+    }
+}
+
+fn parse_color(field: &str) -> Result<Color, &'static str> {
+    match field {
+        "w" => Ok(Color::White),
+        "b" => Ok(Color::Black),
+        _ => Err("invalid active color in FEN"),
+    }
+}
+
+fn parse_castling(field: &str) -> Result<CastlingRights, &'static str> {
+    if field == "-" {
+        return Ok(CastlingRights::none());
+    }
+
+    let mut rights = CastlingRights::none();
+    for c in field.chars() {
+        match c {
+            'K' => rights.grant_kingside(Color::White),
+            'Q' => rights.grant_queenside(Color::White),
+            'k' => rights.grant_kingside(Color::Black),
+            'q' => rights.grant_queenside(Color::Black),
+            _ => return Err("invalid castling availability in FEN"),
+        }
+    }
+
+    Ok(rights)
+}
+
+fn parse_en_passant(field: &str) -> Result<Option<Pos>, &'static str> {
+    if field == "-" {
+        return Ok(None);
+    }
+
+    let mut chars = field.chars();
+    let file = chars.next().ok_or("invalid en passant square in FEN")?;
+    let rank = chars.next().ok_or("invalid en passant square in FEN")?;
+
+    if !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+        return Err("invalid en passant square in FEN");
+    }
+
+    let x = file as u8 - b'a';
+    let y = rank as u8 - b'1';
+
+    Ok(Some(Pos::from_xy(x, y)))
+}
+
+pub fn to_fen(
+    board: &Board,
+    player: Color,
+    castling_rights: CastlingRights,
+    en_passant: Option<Pos>,
+    halfmove_clock: u16,
+    fullmove_number: u16,
+) -> String {
+    let mut ranks = Vec::with_capacity(8);
+
+    for y in (0..8).rev() {
+        ranks.push(rank_to_fen(board, y));
+    }
+
+    let active_color = match player {
+        Color::White => "w",
+        Color::Black => "b",
+    };
+
+    format!(
+        "{} {} {} {} {} {}",
+        ranks.join("/"),
+        active_color,
+        castling_to_fen(castling_rights),
+        en_passant.map_or("-".to_string(), format_square),
+        halfmove_clock,
+        fullmove_number,
+    )
+}
+
+fn rank_to_fen(board: &Board, y: u8) -> String {
+    let mut rank = String::new();
+    let mut empty_run = 0;
+
+    for x in 0..8 {
+        let piece = board.at_xy(x, y);
+        if piece.is_empty() {
+            empty_run += 1;
+            continue;
+        }
+
+        if empty_run > 0 {
+            rank.push_str(&empty_run.to_string());
+            empty_run = 0;
+        }
+
+        rank.push(piece_letter(piece));
+    }
+
+    if empty_run > 0 {
+        rank.push_str(&empty_run.to_string());
+    }
+
+    rank
+}
+
+fn piece_letter(piece: TaggedPiece) -> char {
+    let letter = match piece.get_type() {
+        PieceType::Pawn => 'p',
+        PieceType::Knight => 'n',
+        PieceType::Bishop => 'b',
+        PieceType::Rook => 'r',
+        PieceType::Queen => 'q',
+        PieceType::King => 'k',
+    };
+
+    match piece.color() {
+        Color::White => letter.to_ascii_uppercase(),
+        Color::Black => letter,
+    }
+}
+
+fn castling_to_fen(rights: CastlingRights) -> String {
+    let mut castling = String::new();
+
+    if rights.kingside(Color::White) {
+        castling.push('K');
+    }
+    if rights.queenside(Color::White) {
+        castling.push('Q');
+    }
+    if rights.kingside(Color::Black) {
+        castling.push('k');
+    }
+    if rights.queenside(Color::Black) {
+        castling.push('q');
+    }
+
+    if castling.is_empty() {
+        "-".to_string()
+    } else {
+        castling
+    }
+}
+
+fn format_square(pos: Pos) -> String {
+    let x = pos.index() % 8;
+    let y = pos.index() / 8;
+    format!("{}{}", (b'a' + x as u8) as char, y + 1)
+}
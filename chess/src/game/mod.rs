@@ -1,35 +1,88 @@
 use crate::{Board, Color, Move, MoveMap, PieceType, Pos, TaggedPiece};
 
+mod castling;
+mod fen;
 mod moves;
 mod pgn;
+mod zobrist;
 
 #[cfg(test)]
 mod tests;
 
+pub use castling::CastlingRights;
+
+/// Plies without a pawn move or capture before a fifty-move draw is claimed.
+const FIFTY_MOVE_PLY_LIMIT: u16 = 100;
+
 #[derive(PartialEq, Debug)]
 pub enum GameResult {
     InvalidMove,
     Ok,
     Checkmate,
     Stalemate,
+    DrawByRepetition,
+    DrawByFiftyMove,
+}
+
+/// Everything needed to reverse a single `play`, captured up front instead
+/// of cloning the whole board: `undo` replays these fields in reverse
+/// rather than restoring a snapshot.
+struct UndoInfo {
+    from: Pos,
+    to: Pos,
+    r#move: Move,
+    moved_piece: TaggedPiece,
+    captured: Option<(Pos, TaggedPiece)>,
+    rook_move: Option<(Pos, Pos, TaggedPiece)>,
+    prev_castling_rights: CastlingRights,
+    prev_en_passant: Option<Pos>,
+    prev_halfmove_clock: u16,
+    prev_fullmove_number: u16,
+    prev_king_pos: Pos,
+    /// `key_history`'s length before this move, so a reversible move (one
+    /// that only pushes) can be undone with a cheap `truncate` instead of
+    /// a per-ply clone.
+    prev_key_history_len: usize,
+    /// The tail `key_history` held right before an irreversible move
+    /// (pawn move or capture) wipes it with `clear()`. Only populated for
+    /// such moves, since only they can't be undone by length alone, and
+    /// those are far rarer than the negamax/perft hot path this otherwise
+    /// avoids cloning into.
+    discarded_key_history: Option<Vec<u64>>,
 }
 
 pub struct Game {
     board: Board,
     move_map: MoveMap,
-    history: Vec<(Board, Pos, Move)>,
+    history: Vec<UndoInfo>,
     player: Color,
     king_pos: Pos,
+    castling_rights: CastlingRights,
+    en_passant: Option<Pos>,
+    halfmove_clock: u16,
+    fullmove_number: u16,
+    zobrist_key: u64,
+    key_history: Vec<u64>,
 }
 
 impl Default for Game {
     fn default() -> Self {
+        let castling_rights = CastlingRights::all();
+        let board = Board::default();
+        let zobrist_key = zobrist::position_key(&board, Color::White, castling_rights, None);
+
         let mut game = Game {
-            board: Default::default(),
+            board,
             move_map: MoveMap::new(),
-            history: Vec::<(Board, Pos, Move)>::with_capacity(50),
+            history: Vec::with_capacity(50),
             player: Color::White,
             king_pos: Pos::new_xy(4, 0),
+            castling_rights,
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            zobrist_key,
+            key_history: vec![zobrist_key],
         };
 
         game.calculate_all_moves();
@@ -40,18 +93,67 @@ impl Default for Game {
 
 impl Game {
     pub fn from_board(board: Board, player: Color) -> Self {
+        let castling_rights = CastlingRights::all();
+        let zobrist_key = zobrist::position_key(&board, player, castling_rights, None);
+
         let mut game = Self {
             board: board,
             move_map: MoveMap::new(),
             history: Vec::with_capacity(50),
             player: player,
             king_pos: board.find_king(player),
+            castling_rights,
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            zobrist_key,
+            key_history: vec![zobrist_key],
         };
 
         game.calculate_all_moves();
 
         game
     }
+
+    pub fn from_fen(fen: &str) -> Result<Self, &'static str> {
+        let (board, player, castling_rights, en_passant, halfmove_clock, fullmove_number) =
+            fen::parse_fen(fen)?;
+        let zobrist_key = zobrist::position_key(&board, player, castling_rights, en_passant);
+
+        let mut game = Self {
+            king_pos: board.find_king(player),
+            board,
+            move_map: MoveMap::new(),
+            history: Vec::with_capacity(50),
+            player,
+            castling_rights,
+            en_passant,
+            halfmove_clock,
+            fullmove_number,
+            zobrist_key,
+            key_history: vec![zobrist_key],
+        };
+
+        game.calculate_all_moves();
+
+        Ok(game)
+    }
+
+    pub fn to_fen(&self) -> String {
+        fen::to_fen(
+            &self.board,
+            self.player,
+            self.castling_rights,
+            self.en_passant,
+            self.halfmove_clock,
+            self.fullmove_number,
+        )
+    }
+
+    pub fn zobrist_key(&self) -> u64 {
+        self.zobrist_key
+    }
+
     pub fn switch_side(&mut self) -> bool {
         self.move_map.clear();
         self.player.flip();
@@ -95,18 +197,102 @@ impl Game {
             return GameResult::InvalidMove;
         }
 
-        self.history.push((self.board, from, r#move));
-        self.board = self.board.board_after_move(from, r#move, self.player);
+        let moved_piece = self.at_pos(from);
+        let is_pawn_move = moved_piece.get_type() == PieceType::Pawn;
+        let to = Self::move_destination(from, r#move);
+        let new_castling_rights = self.castling_rights_after_move(from, to, moved_piece);
+        let new_en_passant = self.en_passant_after_move(from, r#move, moved_piece);
+
+        self.zobrist_key = self.zobrist_key_after_move(from, r#move, new_castling_rights, new_en_passant);
+
+        let placed_type = match r#move {
+            Move::PawnPromotion(piece_type, _) => piece_type,
+            _ => moved_piece.get_type(),
+        };
+
+        self.board.place(from, TaggedPiece::empty());
+
+        let en_passant_capture = if let Move::EnPassant(ep_to) = r#move {
+            let captured_pos = Self::en_passant_captured_pos(from, ep_to);
+            let captured_piece = self.board.place(captured_pos, TaggedPiece::empty());
+            Some((captured_pos, captured_piece))
+        } else {
+            None
+        };
+
+        let replaced = self
+            .board
+            .place(to, TaggedPiece::new(placed_type, moved_piece.color()));
+        let direct_capture = (!replaced.is_empty()).then_some((to, replaced));
+        let captured = en_passant_capture.or(direct_capture);
+
+        let rook_move = if let Move::KingSideCastling | Move::QueenSideCastling = r#move {
+            let kingside = r#move == Move::KingSideCastling;
+            let (rook_from, rook_to) = Self::castling_rook_squares(from, kingside);
+            let rook_piece = self.board.place(rook_from, TaggedPiece::empty());
+            self.board.place(rook_to, rook_piece);
+            Some((rook_from, rook_to, rook_piece))
+        } else {
+            None
+        };
+
+        let is_capture = captured.is_some();
+        let clears_key_history = is_pawn_move || is_capture;
+        let discarded_key_history = clears_key_history.then(|| self.key_history.clone());
+
+        self.history.push(UndoInfo {
+            from,
+            to,
+            r#move,
+            moved_piece,
+            captured,
+            rook_move,
+            prev_castling_rights: self.castling_rights,
+            prev_en_passant: self.en_passant,
+            prev_halfmove_clock: self.halfmove_clock,
+            prev_fullmove_number: self.fullmove_number,
+            prev_king_pos: self.king_pos,
+            prev_key_history_len: self.key_history.len(),
+            discarded_key_history,
+        });
+
+        self.castling_rights = new_castling_rights;
+        self.en_passant = new_en_passant;
+
+        if clears_key_history {
+            self.halfmove_clock = 0;
+            self.key_history.clear();
+        } else {
+            self.halfmove_clock += 1;
+        }
+        self.key_history.push(self.zobrist_key);
+
+        if self.player == Color::Black {
+            self.fullmove_number += 1;
+        }
 
         if self.switch_side() {
-            if self.board.pos_in_danger(self.king_pos, self.player) {
+            return if self.board.pos_in_danger(self.king_pos, self.player) {
                 GameResult::Checkmate
             } else {
                 GameResult::Stalemate
-            }
-        } else {
-            GameResult::Ok
+            };
+        }
+
+        if self.halfmove_clock >= FIFTY_MOVE_PLY_LIMIT {
+            return GameResult::DrawByFiftyMove;
         }
+
+        let repetitions = self
+            .key_history
+            .iter()
+            .filter(|key| **key == self.zobrist_key)
+            .count();
+        if repetitions >= 3 {
+            return GameResult::DrawByRepetition;
+        }
+
+        GameResult::Ok
     }
 
     pub fn undo(&mut self, steps: usize) -> bool {
@@ -114,18 +300,198 @@ impl Game {
             return false;
         }
 
-        self.board = self.history[self.history.len() - 1 - steps].0;
-        self.history.truncate(self.history.len() - steps);
-
-        if steps % 2 != 0 {
-            self.player.flip();
+        for _ in 0..=steps {
+            let info = self.history.pop().unwrap();
+            self.unmake(info);
         }
 
-        self.king_pos = self.board.find_king(self.player);
         let _ = self.calculate_all_moves();
         true
     }
 
+    fn unmake(&mut self, info: UndoInfo) {
+        self.board.place(info.to, TaggedPiece::empty());
+        self.board.place(info.from, info.moved_piece);
+
+        if let Some((pos, piece)) = info.captured {
+            self.board.place(pos, piece);
+        }
+
+        if let Some((rook_from, rook_to, rook_piece)) = info.rook_move {
+            self.board.place(rook_to, TaggedPiece::empty());
+            self.board.place(rook_from, rook_piece);
+        }
+
+        self.castling_rights = info.prev_castling_rights;
+        self.en_passant = info.prev_en_passant;
+        self.halfmove_clock = info.prev_halfmove_clock;
+        self.fullmove_number = info.prev_fullmove_number;
+        self.king_pos = info.prev_king_pos;
+
+        match info.discarded_key_history {
+            Some(prev_key_history) => self.key_history = prev_key_history,
+            None => self.key_history.truncate(info.prev_key_history_len),
+        }
+        self.zobrist_key = *self.key_history.last().unwrap();
+        self.player.flip();
+    }
+
+    fn captured_square(&self, from: Pos, r#move: Move) -> Option<(Pos, PieceType, Color)> {
+        match r#move {
+            Move::Move(to) | Move::PawnPromotion(_, to) => {
+                let target = self.at_pos(to);
+                if target.is_empty() {
+                    None
+                } else {
+                    Some((to, target.get_type(), target.color()))
+                }
+            }
+            Move::EnPassant(to) => {
+                let captured_pos = Self::en_passant_captured_pos(from, to);
+                let target = self.at_pos(captured_pos);
+                Some((captured_pos, target.get_type(), target.color()))
+            }
+            Move::KingSideCastling | Move::QueenSideCastling | Move::None => None,
+        }
+    }
+
+    fn en_passant_captured_pos(from: Pos, to: Pos) -> Pos {
+        let rank_base = (from.index() / 8) * 8;
+        let file = to.index() % 8;
+        Pos::new_index((rank_base + file) as u8)
+    }
+
+    fn move_destination(from: Pos, r#move: Move) -> Pos {
+        match r#move {
+            Move::Move(to) | Move::EnPassant(to) | Move::PawnPromotion(_, to) => to,
+            Move::KingSideCastling => Self::castling_king_target(from, true),
+            Move::QueenSideCastling => Self::castling_king_target(from, false),
+            Move::None => from,
+        }
+    }
+
+    fn castling_king_target(from: Pos, kingside: bool) -> Pos {
+        let rank_base = (from.index() / 8) * 8;
+        Pos::new_index((rank_base + if kingside { 6 } else { 2 }) as u8)
+    }
+
+    fn castling_rook_squares(from: Pos, kingside: bool) -> (Pos, Pos) {
+        let rank_base = (from.index() / 8) * 8;
+        if kingside {
+            (
+                Pos::new_index((rank_base + 7) as u8),
+                Pos::new_index((rank_base + 5) as u8),
+            )
+        } else {
+            (
+                Pos::new_index(rank_base as u8),
+                Pos::new_index((rank_base + 3) as u8),
+            )
+        }
+    }
+
+    fn en_passant_after_move(&self, from: Pos, r#move: Move, moved_piece: TaggedPiece) -> Option<Pos> {
+        if moved_piece.get_type() != PieceType::Pawn {
+            return None;
+        }
+
+        if let Move::Move(to) = r#move {
+            let from_rank = from.index() / 8;
+            let to_rank = to.index() / 8;
+            let rank_diff = from_rank.abs_diff(to_rank);
+
+            if rank_diff == 2 {
+                let mid_rank = (from_rank + to_rank) / 2;
+                return Some(Pos::new_index((mid_rank * 8 + from.index() % 8) as u8));
+            }
+        }
+
+        None
+    }
+
+    fn castling_rights_after_move(&self, from: Pos, to: Pos, moved_piece: TaggedPiece) -> CastlingRights {
+        let mut rights = self.castling_rights;
+
+        match moved_piece.get_type() {
+            PieceType::King => rights.revoke_all(moved_piece.color()),
+            PieceType::Rook => {
+                let file = from.index() % 8;
+                if file == 0 {
+                    rights.revoke_queenside(moved_piece.color());
+                } else if file == 7 {
+                    rights.revoke_kingside(moved_piece.color());
+                }
+            }
+            _ => {}
+        }
+
+        // A rook captured on its home corner can no longer castle with,
+        // even if it never moved itself.
+        let captured = self.at_pos(to);
+        if !captured.is_empty() && captured.get_type() == PieceType::Rook {
+            let opponent = captured.color();
+            let home_rank = match opponent {
+                Color::White => 0,
+                Color::Black => 7,
+            };
+
+            if to.index() / 8 == home_rank {
+                match to.index() % 8 {
+                    0 => rights.revoke_queenside(opponent),
+                    7 => rights.revoke_kingside(opponent),
+                    _ => {}
+                }
+            }
+        }
+
+        rights
+    }
+
+    fn zobrist_key_after_move(
+        &self,
+        from: Pos,
+        r#move: Move,
+        new_castling_rights: CastlingRights,
+        new_en_passant: Option<Pos>,
+    ) -> u64 {
+        let mut key = self.zobrist_key;
+        let moved_piece = self.at_pos(from);
+
+        key ^= zobrist::piece_key(moved_piece.get_type(), moved_piece.color(), from);
+
+        if let Some((captured_pos, captured_type, captured_color)) = self.captured_square(from, r#move) {
+            key ^= zobrist::piece_key(captured_type, captured_color, captured_pos);
+        }
+
+        let to = Self::move_destination(from, r#move);
+        let placed_type = match r#move {
+            Move::PawnPromotion(piece_type, _) => piece_type,
+            _ => moved_piece.get_type(),
+        };
+        key ^= zobrist::piece_key(placed_type, moved_piece.color(), to);
+
+        if let Move::KingSideCastling | Move::QueenSideCastling = r#move {
+            let kingside = r#move == Move::KingSideCastling;
+            let (rook_from, rook_to) = Self::castling_rook_squares(from, kingside);
+            key ^= zobrist::piece_key(PieceType::Rook, moved_piece.color(), rook_from);
+            key ^= zobrist::piece_key(PieceType::Rook, moved_piece.color(), rook_to);
+        }
+
+        key ^= zobrist::castling_key(self.castling_rights);
+        key ^= zobrist::castling_key(new_castling_rights);
+
+        if let Some(pos) = self.en_passant {
+            key ^= zobrist::en_passant_key((pos.index() % 8) as u8);
+        }
+        if let Some(pos) = new_en_passant {
+            key ^= zobrist::en_passant_key((pos.index() % 8) as u8);
+        }
+
+        key ^= zobrist::side_to_move_key();
+
+        key
+    }
+
     pub fn at_xy(&self, x: u8, y: u8) -> TaggedPiece {
         self.board.at_xy(x, y)
     }
@@ -142,17 +508,49 @@ impl Game {
         &self.board
     }
 
-    pub fn history(&self, steps: usize) -> &(Board, Pos, Move) {
+    pub fn history(&self, steps: usize) -> (Pos, Move) {
         assert!(steps < self.history.len());
-        &self.history[self.history.len() - 1 - steps]
+        let info = &self.history[self.history.len() - 1 - steps];
+        (info.from, info.r#move)
     }
 
     pub fn history_len(&self) -> usize {
         self.history.len()
     }
 
-    pub fn history_iter(&self) -> std::slice::Iter<'_, (Board, Pos, Move)> {
-        self.history.iter()
+    pub fn history_iter(&self) -> impl Iterator<Item = (Pos, Move)> + '_ {
+        self.history.iter().map(|info| (info.from, info.r#move))
+    }
+
+    pub fn halfmove_clock(&self) -> u16 {
+        self.halfmove_clock
+    }
+
+    pub fn castling_rights(&self) -> CastlingRights {
+        self.castling_rights
+    }
+
+    pub fn en_passant(&self) -> Option<Pos> {
+        self.en_passant
+    }
+
+    pub fn in_check(&self) -> bool {
+        self.board.pos_in_danger(self.king_pos, self.player)
+    }
+
+    /// All legal `(from, move)` pairs for the side to move, flattened out of
+    /// the per-square move map built by `calculate_all_moves`.
+    pub fn legal_moves(&self) -> Vec<(Pos, Move)> {
+        let mut moves = Vec::new();
+
+        for i in 0..64u8 {
+            let pos = Pos::new_index(i);
+            if let Some(pos_moves) = self.move_map.at(pos) {
+                moves.extend(pos_moves.iter().map(|&m| (pos, m)));
+            }
+        }
+
+        moves
     }
 
     fn calculate_all_moves(&mut self) -> bool {
@@ -0,0 +1,41 @@
+use super::*;
+
+#[test]
+fn incremental_zobrist_matches_recomputed_from_scratch() {
+    let mut game = Game::default();
+    let (from, r#move) = game.legal_moves()[0];
+    game.play(from, r#move);
+
+    let recomputed = zobrist::position_key(
+        game.board(),
+        game.current_color(),
+        game.castling_rights(),
+        game.en_passant(),
+    );
+
+    assert_eq!(game.zobrist_key(), recomputed);
+}
+
+#[test]
+fn undo_restores_board_and_zobrist_key() {
+    let mut game = Game::default();
+    let original_fen = game.to_fen();
+    let original_key = game.zobrist_key();
+
+    let (from, r#move) = game.legal_moves()[0];
+    game.play(from, r#move);
+    assert_ne!(game.zobrist_key(), original_key);
+
+    game.undo(0);
+
+    assert_eq!(game.to_fen(), original_fen);
+    assert_eq!(game.zobrist_key(), original_key);
+}
+
+#[test]
+fn fen_round_trips_through_parse_and_export() {
+    let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3";
+    let game = Game::from_fen(fen).unwrap();
+
+    assert_eq!(game.to_fen(), fen);
+}
@@ -0,0 +1,141 @@
+use std::sync::OnceLock;
+
+use crate::{Board, Color, PieceType, Pos};
+
+use super::castling::CastlingRights;
+
+const PIECE_KIND_COUNT: usize = 12;
+const SQUARE_COUNT: usize = 64;
+
+struct ZobristKeys {
+    pieces: [[u64; SQUARE_COUNT]; PIECE_KIND_COUNT],
+    side_to_move: u64,
+    castling: [u64; 4],
+    en_passant_file: [u64; 8],
+}
+
+static KEYS: OnceLock<ZobristKeys> = OnceLock::new();
+
+fn keys() -> &'static ZobristKeys {
+    KEYS.get_or_init(ZobristKeys::generate)
+}
+
+// A small, fixed-seed splitmix64 generator so the table is deterministic
+// across runs instead of depending on an external rng crate.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl ZobristKeys {
+    fn generate() -> Self {
+        let mut rng = SplitMix64(0x2545F4914F6CDD1D);
+
+        let mut pieces = [[0u64; SQUARE_COUNT]; PIECE_KIND_COUNT];
+        for kind in pieces.iter_mut() {
+            for square in kind.iter_mut() {
+                *square = rng.next();
+            }
+        }
+
+        let side_to_move = rng.next();
+        let castling = [rng.next(), rng.next(), rng.next(), rng.next()];
+
+        let mut en_passant_file = [0u64; 8];
+        for file in en_passant_file.iter_mut() {
+            *file = rng.next();
+        }
+
+        ZobristKeys {
+            pieces,
+            side_to_move,
+            castling,
+            en_passant_file,
+        }
+    }
+}
+
+fn piece_index(piece_type: PieceType, color: Color) -> usize {
+    let base = match piece_type {
+        PieceType::Pawn => 0,
+        PieceType::Knight => 1,
+        PieceType::Bishop => 2,
+        PieceType::Rook => 3,
+        PieceType::Queen => 4,
+        PieceType::King => 5,
+    };
+
+    match color {
+        Color::White => base,
+        Color::Black => base + 6,
+    }
+}
+
+pub fn piece_key(piece_type: PieceType, color: Color, pos: Pos) -> u64 {
+    keys().pieces[piece_index(piece_type, color)][pos.index()]
+}
+
+pub fn side_to_move_key() -> u64 {
+    keys().side_to_move
+}
+
+pub fn castling_key(rights: CastlingRights) -> u64 {
+    let mut key = 0;
+
+    if rights.kingside(Color::White) {
+        key ^= keys().castling[0];
+    }
+    if rights.queenside(Color::White) {
+        key ^= keys().castling[1];
+    }
+    if rights.kingside(Color::Black) {
+        key ^= keys().castling[2];
+    }
+    if rights.queenside(Color::Black) {
+        key ^= keys().castling[3];
+    }
+
+    key
+}
+
+pub fn en_passant_key(file: u8) -> u64 {
+    keys().en_passant_file[file as usize]
+}
+
+// Hashes a full position from scratch. Used to seed a new `Game`; `undo`
+// does not call it, but takes the previous key back from the game's key
+// history.
+pub fn position_key(
+    board: &Board,
+    player: Color,
+    castling_rights: CastlingRights,
+    en_passant: Option<Pos>,
+) -> u64 {
+    let mut key = 0u64;
+
+    for i in 0..64u8 {
+        let piece = board.at_index(i as usize);
+        if !piece.is_empty() {
+            key ^= piece_key(piece.get_type(), piece.color(), Pos::new_index(i));
+        }
+    }
+
+    if player == Color::Black {
+        key ^= side_to_move_key();
+    }
+
+    key ^= castling_key(castling_rights);
+
+    if let Some(pos) = en_passant {
+        key ^= en_passant_key((pos.index() % 8) as u8);
+    }
+
+    key
+}
@@ -0,0 +1,181 @@
+//! A minimal Universal Chess Interface front-end over stdin/stdout, so the
+//! engine can be plugged into any standard chess GUI.
+
+use crate::game::{Game, GameResult};
+use crate::{Move, PieceType, Pos};
+use std::io::{self, BufRead, Write};
+
+const ENGINE_NAME: &str = "antlilja-chess";
+const ENGINE_AUTHOR: &str = "MarkusVidefors";
+
+/// Search depth used for `go`. The engine has no iterative deepening or
+/// time management yet, so `movetime` is accepted but falls back to this
+/// same fixed depth rather than actually bounding search time.
+const DEFAULT_DEPTH: u8 = 4;
+
+/// Reads UCI commands from stdin until `quit` or end of input, replying on
+/// stdout. Unrecognised commands are silently ignored, per the protocol.
+pub fn run() {
+    let mut game = Game::default();
+
+    for line in io::stdin().lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("uci") => {
+                println!("id name {}", ENGINE_NAME);
+                println!("id author {}", ENGINE_AUTHOR);
+                println!("uciok");
+            }
+            Some("isready") => println!("readyok"),
+            Some("ucinewgame") => game = Game::default(),
+            Some("position") => {
+                let args: Vec<&str> = tokens.collect();
+                handle_position(&mut game, &args);
+            }
+            Some("go") => {
+                let args: Vec<&str> = tokens.collect();
+                handle_go(&mut game, &args);
+            }
+            Some("quit") => break,
+            _ => {}
+        }
+
+        let _ = io::stdout().flush();
+    }
+}
+
+fn handle_position(game: &mut Game, args: &[&str]) {
+    let moves_at = args.iter().position(|&arg| arg == "moves");
+    let setup = &args[..moves_at.unwrap_or(args.len())];
+
+    let new_game = match setup {
+        ["startpos"] => Game::default(),
+        _ if setup.first() == Some(&"fen") => match Game::from_fen(&setup[1..].join(" ")) {
+            Ok(game) => game,
+            Err(_) => return,
+        },
+        _ => return,
+    };
+    *game = new_game;
+
+    if let Some(moves_at) = moves_at {
+        for mv in &args[moves_at + 1..] {
+            let _ = apply_long_algebraic(game, mv);
+        }
+    }
+}
+
+fn handle_go(game: &mut Game, args: &[&str]) {
+    let depth = args
+        .iter()
+        .position(|&arg| arg == "depth")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|depth| depth.parse().ok())
+        .unwrap_or(DEFAULT_DEPTH);
+
+    match game.best_move(depth) {
+        Some((from, r#move)) => {
+            game.play(from, r#move);
+            println!("bestmove {}", format_long_algebraic(from, r#move));
+        }
+        None => println!("bestmove 0000"),
+    }
+}
+
+/// Parses and plays a long-algebraic move such as `e2e4` or `e7e8q` by
+/// matching it against the position's legal moves, rather than re-deriving
+/// move semantics here.
+fn apply_long_algebraic(game: &mut Game, mv: &str) -> Result<(), &'static str> {
+    if mv.len() < 4 {
+        return Err("move is too short to be long algebraic notation");
+    }
+
+    let from = parse_square(&mv[0..2]).ok_or("invalid origin square")?;
+    let to = parse_square(&mv[2..4]).ok_or("invalid target square")?;
+    let promotion = mv.chars().nth(4).and_then(parse_promotion);
+
+    let moves = game.moves_for_pos(from).ok_or("no legal moves from that square")?;
+    let matched = moves
+        .iter()
+        .copied()
+        .find(|&candidate| move_matches(from, candidate, to, promotion))
+        .ok_or("move is not legal in this position")?;
+
+    match game.play(from, matched) {
+        GameResult::InvalidMove => Err("engine rejected a move it had just reported as legal"),
+        _ => Ok(()),
+    }
+}
+
+fn move_matches(from: Pos, r#move: Move, to: Pos, promotion: Option<PieceType>) -> bool {
+    match r#move {
+        Move::Move(target) | Move::EnPassant(target) => target == to && promotion.is_none(),
+        Move::PawnPromotion(piece_type, target) => target == to && promotion == Some(piece_type),
+        Move::KingSideCastling => to == castling_target(from, true),
+        Move::QueenSideCastling => to == castling_target(from, false),
+        Move::None => false,
+    }
+}
+
+fn castling_target(from: Pos, kingside: bool) -> Pos {
+    let rank_base = (from.index() / 8) * 8;
+    Pos::new_index((rank_base + if kingside { 6 } else { 2 }) as u8)
+}
+
+fn format_long_algebraic(from: Pos, r#move: Move) -> String {
+    let to = match r#move {
+        Move::Move(target) | Move::EnPassant(target) | Move::PawnPromotion(_, target) => target,
+        Move::KingSideCastling => castling_target(from, true),
+        Move::QueenSideCastling => castling_target(from, false),
+        Move::None => from,
+    };
+
+    let mut notation = format!("{}{}", format_square(from), format_square(to));
+    if let Move::PawnPromotion(piece_type, _) = r#move {
+        notation.push(promotion_letter(piece_type));
+    }
+
+    notation
+}
+
+fn parse_square(field: &str) -> Option<Pos> {
+    let mut chars = field.chars();
+    let file = chars.next()?;
+    let rank = chars.next()?;
+
+    if !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+        return None;
+    }
+
+    Some(Pos::new_xy(file as u8 - b'a', rank as u8 - b'1'))
+}
+
+fn parse_promotion(c: char) -> Option<PieceType> {
+    match c.to_ascii_lowercase() {
+        'n' => Some(PieceType::Knight),
+        'b' => Some(PieceType::Bishop),
+        'r' => Some(PieceType::Rook),
+        'q' => Some(PieceType::Queen),
+        _ => None,
+    }
+}
+
+fn format_square(pos: Pos) -> String {
+    let x = pos.index() % 8;
+    let y = pos.index() / 8;
+    format!("{}{}", (b'a' + x as u8) as char, y + 1)
+}
+
+fn promotion_letter(piece_type: PieceType) -> char {
+    match piece_type {
+        PieceType::Knight => 'n',
+        PieceType::Bishop => 'b',
+        PieceType::Rook => 'r',
+        PieceType::Queen | PieceType::King | PieceType::Pawn => 'q',
+    }
+}
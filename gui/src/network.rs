@@ -1,3 +1,4 @@
+use chess::game::Game;
 use chess::{Move, PieceType, Pos};
 use std::collections::VecDeque;
 use std::io::prelude::*;
@@ -27,13 +28,15 @@ impl MoveType {
         }
     }
 
-    pub fn from_bytes(bytes: [u8; 4]) -> Result<MoveType, &'static str> {
-        match bytes[0] {
-            0x0 => Ok(MoveType::Standard(bytes[1], bytes[2])),
-            0x1 => Ok(MoveType::EnPassant(bytes[1], bytes[2])),
-            0x2 => Ok(MoveType::Promotion(bytes[1], bytes[2], bytes[3])),
-            0x3 => Ok(MoveType::KingsideCastle),
-            0x4 => Ok(MoveType::QueensideCastle),
+    pub fn from_payload(payload: &[u8]) -> Result<MoveType, &'static str> {
+        match payload.first() {
+            Some(0x0) if payload.len() == 3 => Ok(MoveType::Standard(payload[1], payload[2])),
+            Some(0x1) if payload.len() == 3 => Ok(MoveType::EnPassant(payload[1], payload[2])),
+            Some(0x2) if payload.len() == 4 => {
+                Ok(MoveType::Promotion(payload[1], payload[2], payload[3]))
+            }
+            Some(0x3) => Ok(MoveType::KingsideCastle),
+            Some(0x4) => Ok(MoveType::QueensideCastle),
             _ => Err("Byte is not valid move type"),
         }
     }
@@ -99,7 +102,7 @@ impl MoveType {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Message {
     Decline,
     Move(MoveType),
@@ -108,58 +111,102 @@ pub enum Message {
     Checkmate,
     Draw,
     Resign,
+    Sync(Vec<u8>),
 }
 
 impl Message {
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = vec![0x0];
+        let mut payload = Vec::new();
 
-        match self {
-            Message::Decline => bytes[0] = 0x0,
+        let tag = match self {
+            Message::Decline => 0x0,
             Message::Move(move_type) => {
-                bytes[0] = 0x1;
-                bytes.append(&mut move_type.to_bytes());
+                payload = move_type.to_bytes();
+                0x1
+            }
+            Message::Undo => 0x2,
+            Message::Accept => 0x3,
+            Message::Checkmate => 0x4,
+            Message::Draw => 0x5,
+            Message::Resign => 0x6,
+            Message::Sync(state) => {
+                payload = state.clone();
+                0x7
             }
-            Message::Undo => bytes[0] = 0x2,
-            Message::Accept => bytes[0] = 0x3,
-            Message::Checkmate => bytes[0] = 0x4,
-            Message::Draw => bytes[0] = 0x5,
-            Message::Resign => bytes[0] = 0x6,
         };
 
+        let mut bytes = vec![tag, payload.len() as u8];
+        bytes.append(&mut payload);
+
         bytes
     }
 
-    pub fn from_bytes(bytes: [u8; 5]) -> Result<Self, &'static str> {
-        match bytes[0] {
+    pub fn from_frame(frame: &[u8]) -> Result<Self, &'static str> {
+        let tag = *frame.first().ok_or("frame is missing tag byte")?;
+        let payload = frame.get(2..).ok_or("frame is missing length byte")?;
+
+        match tag {
             0x0 => Ok(Message::Decline),
-            0x1 => {
-                let move_bytes: [u8; 4] = [bytes[1], bytes[2], bytes[3], bytes[4]];
-                Ok(Message::Move(MoveType::from_bytes(move_bytes).unwrap()))
-            }
+            0x1 => Ok(Message::Move(MoveType::from_payload(payload)?)),
             0x2 => Ok(Message::Undo),
             0x3 => Ok(Message::Accept),
             0x4 => Ok(Message::Checkmate),
             0x5 => Ok(Message::Draw),
             0x6 => Ok(Message::Resign),
+            0x7 => Ok(Message::Sync(payload.to_vec())),
             _ => Err("Byte is not valid message type"),
         }
     }
-    
+
     pub fn from_chess_move(origin: Pos, r#move: Move) -> Result<Self, &'static str> {
         let move_type = MoveType::from_chess_move(origin, r#move);
         Ok(Message::Move(move_type.unwrap()))
     }
+
+    /// Builds a `Sync` message carrying `game`'s position as FEN bytes, so
+    /// a peer that joins late or reconnects can reconstruct an identical
+    /// `Game` instead of assuming the standard opening.
+    pub fn from_game(game: &Game) -> Self {
+        Message::Sync(game.to_fen().into_bytes())
+    }
+
+    /// Reconstructs the `Game` carried by a `Sync` message, if this is one.
+    pub fn to_game(&self) -> Option<Result<Game, &'static str>> {
+        match self {
+            Message::Sync(fen_bytes) => Some(
+                std::str::from_utf8(fen_bytes)
+                    .map_err(|_| "Sync payload is not valid UTF-8")
+                    .and_then(Game::from_fen),
+            ),
+            _ => None,
+        }
+    }
 }
 
 pub struct ConnectionHandler {
     pub is_host: bool,
     stream: Arc<Mutex<TcpStream>>,
-    pub recieved_messages: Arc<Mutex<VecDeque<[u8; 5]>>>,
+    pub recieved_messages: Arc<Mutex<VecDeque<Vec<u8>>>>,
     read_handle: Option<thread::JoinHandle<()>>,
     pub last_sent: Option<Message>,
 }
 
+/// Pulls one complete `[tag, len, ...payload]` frame off the front of `buffer`, if present.
+fn take_frame(buffer: &mut Vec<u8>) -> Option<Vec<u8>> {
+    if buffer.len() < 2 {
+        return None;
+    }
+
+    let payload_len = buffer[1] as usize;
+    let frame_len = 2 + payload_len;
+
+    if buffer.len() < frame_len {
+        return None;
+    }
+
+    Some(buffer.drain(..frame_len).collect())
+}
+
 impl ConnectionHandler {
     pub fn new(stream: TcpStream, is_host: bool) -> Self {
         stream.set_read_timeout(Some(std::time::Duration::from_millis(10))).unwrap();
@@ -183,43 +230,54 @@ impl ConnectionHandler {
         Self::new(stream, false)
     }
 
-    pub fn host(port: u16) -> Self {
+    /// Accepts a connection and immediately sends `game`'s current position
+    /// as a `Sync` message, so the joining peer can reconstruct it instead
+    /// of assuming the standard opening.
+    pub fn host(port: u16, game: &Game) -> Self {
         let listener = TcpListener::bind(format!("127.0.0.1:{}", port)).unwrap();
         let (stream, _addr) = listener.accept().unwrap();
 
-        Self::new(stream, true)
+        let mut handler = Self::new(stream, true);
+        handler.write_message(Message::from_game(game)).ok();
+        handler
     }
 
     fn spawn_read_thread(&mut self) -> thread::JoinHandle<()> {
         let stream = Arc::clone(&self.stream);
         let queue = Arc::clone(&self.recieved_messages);
 
-        thread::spawn(move || loop {
-            let mut buf = [0; 5];
+        thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let mut buf = [0; 256];
 
-            let mut stream = stream.lock().unwrap();
-            let mut queue = queue.lock().unwrap();
+            loop {
+                let mut stream = stream.lock().unwrap();
 
-            match stream.read(&mut buf) {
-                Ok(result) => {
-                    if result == 0 {
-                        break;
-                    } else {
-                        queue.push_front(buf);
+                match stream.read(&mut buf) {
+                    Ok(result) => {
+                        if result == 0 {
+                            break;
+                        } else {
+                            buffer.extend_from_slice(&buf[..result]);
+                        }
                     }
-                }
-                Err(e) => {
-                    match e.kind() {
-                        std::io::ErrorKind::WouldBlock => (),
-                        _ => {
-                            panic!("Recieved error when reading stream buffer: {:?}", e);
+                    Err(e) => {
+                        match e.kind() {
+                            std::io::ErrorKind::WouldBlock => (),
+                            _ => {
+                                panic!("Recieved error when reading stream buffer: {:?}", e);
+                            }
                         }
                     }
                 }
-            }
 
-            drop(stream);
-            drop(queue);
+                drop(stream);
+
+                let mut queue = queue.lock().unwrap();
+                while let Some(frame) = take_frame(&mut buffer) {
+                    queue.push_front(frame);
+                }
+            }
         })
     }
 
@@ -1,23 +1,43 @@
+use std::sync::OnceLock;
+
 use crate::piece::{Color, ColoredPiece, PieceType};
 use crate::pos::Pos;
 use crate::r#move::Move;
 
-pub struct Board([ColoredPiece; 64]);
+const PIECE_KIND_COUNT: usize = 12;
+
+const FILE_A: u64 = 0x0101010101010101;
+const FILE_H: u64 = 0x8080808080808080;
+
+const PIECE_TYPES: [PieceType; 6] = [
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
+
+pub struct Board {
+    pieces: [u64; PIECE_KIND_COUNT],
+    occupancy: [u64; 2],
+}
 
 impl Board {
     pub fn new() -> Self {
         let mut board = Board {
-            0: [ColoredPiece::empty(); 64],
+            pieces: [0; PIECE_KIND_COUNT],
+            occupancy: [0; 2],
         };
 
         for i in 0..8 {
-            board.0[8 + i] = ColoredPiece::new(PieceType::Pawn, Color::White);
-            board.0[8 * 6 + i] = ColoredPiece::new(PieceType::Pawn, Color::Black);
+            board.set(8 + i, ColoredPiece::new(PieceType::Pawn, Color::White));
+            board.set(8 * 6 + i, ColoredPiece::new(PieceType::Pawn, Color::Black));
         }
 
         let mut place_at_both_sides = |offset, r#type| {
-            board.0[offset] = ColoredPiece::new(r#type, Color::White);
-            board.0[8 * 7 + offset] = ColoredPiece::new(r#type, Color::Black);
+            board.set(offset, ColoredPiece::new(r#type, Color::White));
+            board.set(8 * 7 + offset, ColoredPiece::new(r#type, Color::Black));
         };
 
         let mut place_matching_at_both_sides = |offset, r#type| {
@@ -34,26 +54,46 @@ impl Board {
         return board;
     }
 
+    fn set(&mut self, square: u8, piece: ColoredPiece) {
+        let bit = 1u64 << square;
+        self.pieces[piece_index(piece.get_type(), piece.get_color())] |= bit;
+        self.occupancy[color_index(piece.get_color())] |= bit;
+    }
+
     pub fn at(&self, x: u8, y: u8) -> ColoredPiece {
-        return self.0[(y * 8 + x) as usize];
+        self.at_index(y * 8 + x)
     }
 
     pub fn at_index(&self, i: u8) -> ColoredPiece {
-        return self.0[i as usize];
+        let bit = 1u64 << i;
+
+        for (index, bitboard) in self.pieces.iter().enumerate() {
+            if bitboard & bit != 0 {
+                let color = if index < 6 { Color::White } else { Color::Black };
+                return ColoredPiece::new(PIECE_TYPES[index % 6], color);
+            }
+        }
+
+        ColoredPiece::empty()
     }
+
     pub fn get_moves_for(&self, buffer: &mut Vec<Move>, x: u8, y: u8) -> usize {
         let piece = self.at(x, y);
 
+        if piece.is_empty() {
+            return 0;
+        }
+
         match piece.get_type() {
-            PieceType::Pawn => {
-                return self.add_pawn_moves(buffer, piece.get_color(), x, y);
-            }
-            PieceType::Rook => {
-                return self.add_rook_moves(buffer, piece.get_color(), x, y);
-            }
-            _ => {
-                return 0;
+            PieceType::Pawn => self.add_pawn_moves(buffer, piece.get_color(), x, y),
+            PieceType::Knight => self.add_knight_moves(buffer, piece.get_color(), x, y),
+            PieceType::Bishop => self.add_diagonal_moves(buffer, piece.get_color(), x, y),
+            PieceType::Rook => self.add_straight_moves(buffer, piece.get_color(), x, y),
+            PieceType::Queen => {
+                self.add_straight_moves(buffer, piece.get_color(), x, y)
+                    + self.add_diagonal_moves(buffer, piece.get_color(), x, y)
             }
+            PieceType::King => self.add_king_moves(buffer, piece.get_color(), x, y),
         }
     }
 
@@ -62,95 +102,274 @@ impl Board {
             return 0;
         }
 
-        let from = Pos::from_xy(x, y);
+        let square = y * 8 + x;
+        let from_bit = 1u64 << square;
+        let occupancy = self.occupancy[0] | self.occupancy[1];
+        let enemy = self.occupancy[1 - color_index(color)];
 
-        let mut count: usize = 0;
-        let mut add_pawn_move = |to| {
-            buffer.push(Move::Move(from, to));
-            count += 1;
+        let (push, home_rank, left_capture, right_capture) = match color {
+            Color::White => (
+                from_bit << 8,
+                1,
+                (from_bit & !FILE_A) << 7,
+                (from_bit & !FILE_H) << 9,
+            ),
+            Color::Black => (
+                from_bit >> 8,
+                6,
+                (from_bit & !FILE_H) >> 7,
+                (from_bit & !FILE_A) >> 9,
+            ),
         };
 
-        let dir: i8 = if color == Color::White { 1 } else { -1 };
-
-        let y_forward = (y as i8 + dir) as u8;
-        if self.at(x, y_forward).is_empty() {
-            add_pawn_move(Pos::from_xy(x, y_forward));
+        let mut count = 0;
 
-            let y_off = y as i8 + dir * 2;
-            if (0..8).contains(&y_off) {
-                let y_off = y_off as u8;
+        let single_push = push & !occupancy;
+        if single_push != 0 {
+            count += self.push_moves_from_bitboard(buffer, x, y, single_push);
 
-                if (y == 1 || y == 6) && self.at(x, y_off).is_empty() {
-                    add_pawn_move(Pos::from_xy(x, y_off));
-                }
+            if y == home_rank {
+                let double_push = match color {
+                    Color::White => single_push << 8,
+                    Color::Black => single_push >> 8,
+                } & !occupancy;
+                count += self.push_moves_from_bitboard(buffer, x, y, double_push);
             }
         }
 
-        let mut add_pawn_take = |x: u8, y: u8| {
-            let space = self.at(x, y);
-            if !space.is_empty() && space.get_color() != color {
-                add_pawn_move(Pos::from_xy(x, y));
-            }
-        };
+        let captures = (left_capture | right_capture) & enemy;
+        count += self.push_moves_from_bitboard(buffer, x, y, captures);
 
-        if x != 7 {
-            add_pawn_take(x + 1, y_forward);
-        }
+        count
+    }
 
-        if x != 0 {
-            add_pawn_take(x - 1, y_forward);
-        }
+    fn add_straight_moves(&self, buffer: &mut Vec<Move>, color: Color, x: u8, y: u8) -> usize {
+        let square = (y * 8 + x) as usize;
+        let occupancy = self.occupancy[0] | self.occupancy[1];
+        let own = self.occupancy[color_index(color)];
+        let rays = &tables().straight_rays;
+
+        let mut attacks = 0;
+        attacks |= positive_ray_attacks(rays[0][square], occupancy);
+        attacks |= positive_ray_attacks(rays[1][square], occupancy);
+        attacks |= negative_ray_attacks(rays[2][square], occupancy);
+        attacks |= negative_ray_attacks(rays[3][square], occupancy);
 
-        return count;
+        self.push_moves_from_bitboard(buffer, x, y, attacks & !own)
     }
 
-    fn add_rook_moves(&self, buffer: &mut Vec<Move>, color: Color, x: u8, y: u8) -> usize {
-        let from = Pos::from_xy(x, y);
+    fn add_diagonal_moves(&self, buffer: &mut Vec<Move>, color: Color, x: u8, y: u8) -> usize {
+        let square = (y * 8 + x) as usize;
+        let occupancy = self.occupancy[0] | self.occupancy[1];
+        let own = self.occupancy[color_index(color)];
+        let rays = &tables().diagonal_rays;
 
-        let mut count = 0;
-        let mut loop_internal = |x, y| {
-            let i = y * 8 + x;
-            let space = self.0[i as usize];
-            if space.is_empty() || space.get_color() != color {
-                buffer.push(Move::Move(from, Pos::from_xy(x, y)));
-                count += 1;
-            }
+        let mut attacks = 0;
+        attacks |= positive_ray_attacks(rays[0][square], occupancy);
+        attacks |= positive_ray_attacks(rays[1][square], occupancy);
+        attacks |= negative_ray_attacks(rays[2][square], occupancy);
+        attacks |= negative_ray_attacks(rays[3][square], occupancy);
 
-            return space.is_empty();
-        };
+        self.push_moves_from_bitboard(buffer, x, y, attacks & !own)
+    }
 
-        for x in (x + 1)..8 {
-            if !loop_internal(x, y) {
-                break;
-            }
-        }
+    fn add_knight_moves(&self, buffer: &mut Vec<Move>, color: Color, x: u8, y: u8) -> usize {
+        let square = (y * 8 + x) as usize;
+        let own = self.occupancy[color_index(color)];
+        let attacks = tables().knight_attacks[square] & !own;
 
-        for x in x..0 {
-            if !loop_internal(x, y) {
-                break;
-            }
+        self.push_moves_from_bitboard(buffer, x, y, attacks)
+    }
+
+    fn add_king_moves(&self, buffer: &mut Vec<Move>, color: Color, x: u8, y: u8) -> usize {
+        let square = (y * 8 + x) as usize;
+        let own = self.occupancy[color_index(color)];
+        let attacks = tables().king_attacks[square] & !own;
+
+        self.push_moves_from_bitboard(buffer, x, y, attacks)
+    }
+
+    fn push_moves_from_bitboard(&self, buffer: &mut Vec<Move>, x: u8, y: u8, mut targets: u64) -> usize {
+        let from = Pos::from_xy(x, y);
+        let mut count = 0;
+
+        while targets != 0 {
+            let square = targets.trailing_zeros() as u8;
+            buffer.push(Move::Move(from, Pos::from_xy(square % 8, square / 8)));
+            targets &= targets - 1;
+            count += 1;
         }
 
-        for y in (y + 1)..8 {
-            if !loop_internal(x, y) {
-                break;
-            }
+        count
+    }
+
+    pub fn print(&self) {
+        for y in 0..8 {
+            let row: Vec<ColoredPiece> = (0..8).map(|x| self.at(x, y)).collect();
+            println!("{:?}", row);
         }
+    }
+}
 
-        for y in y..0 {
-            if !loop_internal(x, y) {
-                break;
-            }
+fn piece_index(piece_type: PieceType, color: Color) -> usize {
+    let base = match piece_type {
+        PieceType::Pawn => 0,
+        PieceType::Knight => 1,
+        PieceType::Bishop => 2,
+        PieceType::Rook => 3,
+        PieceType::Queen => 4,
+        PieceType::King => 5,
+    };
+
+    base + color_index(color) * 6
+}
+
+fn color_index(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 1,
+    }
+}
+
+// Squares strictly beyond the nearest blocker are masked off: `trailing_zeros`
+// finds the closest blocker for rays that walk towards higher square indices
+// (north/east/north-east/north-west).
+fn positive_ray_attacks(ray: u64, occupancy: u64) -> u64 {
+    let blockers = ray & occupancy;
+    if blockers == 0 {
+        return ray;
+    }
+
+    let blocker_square = blockers.trailing_zeros();
+    let beyond_blocker = if blocker_square == 63 { 0 } else { u64::MAX << (blocker_square + 1) };
+    ray & !beyond_blocker
+}
+
+// Mirror of `positive_ray_attacks` for rays that walk towards lower square
+// indices (south/west/south-east/south-west), using `leading_zeros` to find
+// the nearest blocker.
+fn negative_ray_attacks(ray: u64, occupancy: u64) -> u64 {
+    let blockers = ray & occupancy;
+    if blockers == 0 {
+        return ray;
+    }
+
+    let blocker_square = 63 - blockers.leading_zeros();
+    let before_blocker = if blocker_square == 0 { 0 } else { (1u64 << blocker_square) - 1 };
+    ray & !before_blocker
+}
+
+struct AttackTables {
+    // [north, east, south, west]
+    straight_rays: [[u64; 64]; 4],
+    // [north-east, north-west, south-east, south-west]
+    diagonal_rays: [[u64; 64]; 4],
+    knight_attacks: [u64; 64],
+    king_attacks: [u64; 64],
+}
+
+static TABLES: OnceLock<AttackTables> = OnceLock::new();
+
+fn tables() -> &'static AttackTables {
+    TABLES.get_or_init(|| AttackTables {
+        straight_rays: generate_straight_rays(),
+        diagonal_rays: generate_diagonal_rays(),
+        knight_attacks: generate_leaper_attacks(&KNIGHT_OFFSETS),
+        king_attacks: generate_leaper_attacks(&KING_OFFSETS),
+    })
+}
+
+fn generate_straight_rays() -> [[u64; 64]; 4] {
+    let mut rays = [[0u64; 64]; 4];
+
+    for square in 0u8..64 {
+        let x = (square % 8) as i8;
+        let y = (square / 8) as i8;
+
+        for ny in (y + 1)..8 {
+            rays[0][square as usize] |= 1u64 << (ny * 8 + x);
         }
+        for nx in (x + 1)..8 {
+            rays[1][square as usize] |= 1u64 << (y * 8 + nx);
+        }
+        for ny in (0..y).rev() {
+            rays[2][square as usize] |= 1u64 << (ny * 8 + x);
+        }
+        for nx in (0..x).rev() {
+            rays[3][square as usize] |= 1u64 << (y * 8 + nx);
+        }
+    }
+
+    rays
+}
 
-        return count;
+fn generate_diagonal_rays() -> [[u64; 64]; 4] {
+    let mut rays = [[0u64; 64]; 4];
+
+    for square in 0u8..64 {
+        let x = (square % 8) as i8;
+        let y = (square / 8) as i8;
+
+        rays[0][square as usize] = ray_along(x, y, 1, 1);
+        rays[1][square as usize] = ray_along(x, y, -1, 1);
+        rays[2][square as usize] = ray_along(x, y, 1, -1);
+        rays[3][square as usize] = ray_along(x, y, -1, -1);
     }
 
-    pub fn print(&self) {
-        for i in 0..8 {
-            let start = i * 8;
-            let end = start + 8;
-            println!("{:?}", &self.0[start..end]);
+    rays
+}
+
+fn ray_along(x: i8, y: i8, dx: i8, dy: i8) -> u64 {
+    let mut bitboard = 0u64;
+    let (mut nx, mut ny) = (x + dx, y + dy);
+
+    while (0..8).contains(&nx) && (0..8).contains(&ny) {
+        bitboard |= 1u64 << (ny * 8 + nx);
+        nx += dx;
+        ny += dy;
+    }
+
+    bitboard
+}
+
+const KNIGHT_OFFSETS: [(i8, i8); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+
+const KING_OFFSETS: [(i8, i8); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+fn generate_leaper_attacks(offsets: &[(i8, i8); 8]) -> [u64; 64] {
+    let mut table = [0u64; 64];
+
+    for square in 0u8..64 {
+        let x = (square % 8) as i8;
+        let y = (square / 8) as i8;
+
+        for (dx, dy) in offsets {
+            let nx = x + dx;
+            let ny = y + dy;
+
+            if (0..8).contains(&nx) && (0..8).contains(&ny) {
+                table[square as usize] |= 1u64 << (ny * 8 + nx);
+            }
         }
     }
+
+    table
 }